@@ -1,9 +1,15 @@
-use std::collections::HashMap;
+use crate::tiebreak::break_tie;
+use crate::{AggregationRule, VoteData};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
 
 // we only need the 'true' votes
-struct ApprovalVoting<'a> {
+pub struct ApprovalVoting<'a> {
     voters: Vec<Vec<&'a str>>,
     ignore: Vec<&'a str>,
+    seed: Option<Vec<u8>>,
 }
 
 impl<'a> ApprovalVoting<'a> {
@@ -11,6 +17,7 @@ impl<'a> ApprovalVoting<'a> {
         Self {
             voters,
             ignore: Vec::new(),
+            seed: None,
         }
     }
 
@@ -18,6 +25,12 @@ impl<'a> ApprovalVoting<'a> {
         self.ignore.push(ignore);
     }
 
+    /// Seeds deterministic tie-breaking (e.g. from `VoteData::hash_sync()`).
+    /// Without a seed, tied candidates are returned together as before.
+    pub fn seed(&mut self, seed: Vec<u8>) {
+        self.seed = Some(seed);
+    }
+
     pub fn calculate(&self) -> Option<Vec<&'a str>> {
         let mut counts: HashMap<&str, u32> = HashMap::new();
 
@@ -50,6 +63,15 @@ impl<'a> ApprovalVoting<'a> {
             return None;
         }
 
+        if let Some(seed) = &self.seed {
+            if winner.len() > 1 {
+                // `ignore.len()` doubles as the round index: it only grows
+                // as successive `Iterator::next()` calls settle a tie
+                let elected = break_tie(seed, self.ignore.len(), &winner, false);
+                return Some(vec![elected]);
+            }
+        }
+
         Some(winner)
     }
 }
@@ -70,12 +92,62 @@ impl<'a> Iterator for ApprovalVoting<'a> {
     }
 }
 
+/// A voter's weight on a policy needs to clear this fraction to count as an
+/// approval, rather than discarding the weight and treating every cast
+/// vote as a flat approval.
+pub const APPROVAL_THRESHOLD: f64 = 0.0;
+
+/// Adapts `VoteData`'s weighted ballots into approval ballots: a policy is
+/// "approved" by a voter once their weight on it clears `APPROVAL_THRESHOLD`.
+fn approval_ballots(vote: &VoteData) -> (BTreeMap<Uuid, String>, Vec<Vec<Uuid>>) {
+    let ids: BTreeMap<Uuid, String> = vote.policies.iter().map(|id| (*id, id.to_string())).collect();
+
+    let ballots = vote
+        .only_policy_voting()
+        .into_values()
+        .map(|choices| {
+            choices
+                .into_iter()
+                .filter(|(_, weight)| *weight > APPROVAL_THRESHOLD)
+                .map(|(id, _)| id)
+                .collect()
+        })
+        .collect();
+
+    (ids, ballots)
+}
+
+#[async_trait]
+impl AggregationRule for ApprovalVoting<'_> {
+    async fn calculate(vote: VoteData) -> Value {
+        let (ids, ballots) = approval_ballots(&vote);
+        let ballots: Vec<Vec<&str>> = ballots
+            .iter()
+            .map(|ballot| ballot.iter().map(|id| ids[id].as_str()).collect())
+            .collect();
+
+        let mut approval = ApprovalVoting::new(ballots);
+        approval.seed(vote.hash_sync());
+
+        let winners: Vec<Uuid> = approval
+            .calculate()
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|id| Uuid::parse_str(id).ok())
+            .collect();
+
+        json!({ "winners": winners })
+    }
+}
+
 #[cfg(test)]
 mod approval_test {
 
     use std::collections::BTreeSet;
 
     use super::*;
+    use crate::TopicData;
+    use futures::executor::block_on;
 
     #[test]
     fn simple() {
@@ -135,6 +207,22 @@ mod approval_test {
         assert_eq!(rcv.calculate(), Some(vec!["dog"]));
     }
 
+    #[test]
+    fn seeded_tie_break_is_deterministic() {
+        let a = vec!["cat"];
+        let b = vec!["dog"];
+
+        let voters: Vec<Vec<&str>> = vec![a, b];
+        let mut rcv = ApprovalVoting::new(voters);
+        rcv.seed(b"published-digest".to_vec());
+
+        let first = rcv.calculate();
+        let second = rcv.calculate();
+
+        assert_eq!(first, second);
+        assert_eq!(first.unwrap().len(), 1);
+    }
+
     #[test]
     fn iterator() {
         let a = vec!["cat", "dog"];
@@ -152,4 +240,24 @@ mod approval_test {
         assert_eq!(set, result);
         assert_eq!(rcv_iter.next(), None);
     }
+
+    #[test]
+    fn aggregation_rule_ignores_non_positive_weight() {
+        let mut topic = TopicData::dummy();
+
+        let alice = topic.get_id_by_name("alice").unwrap();
+        let apples = topic.get_id_by_title("apples").unwrap();
+        let bananas = topic.get_id_by_title("bananas").unwrap();
+
+        // alice withdraws approval from apples but keeps it on bananas
+        topic.cast_vote_to(&alice, &apples, 0.0f64);
+        topic.cast_vote_to(&alice, &bananas, 1.0f64);
+
+        let info: VoteData = topic.into();
+        let result = block_on(<ApprovalVoting as AggregationRule>::calculate(info));
+
+        let winners = result.get("winners").unwrap().as_array().unwrap();
+        assert_eq!(winners.len(), 1);
+        assert_eq!(winners[0].as_str().unwrap(), bananas.to_string());
+    }
 }
@@ -1,4 +1,8 @@
-use std::collections::HashMap;
+use crate::{AggregationRule, VoteData};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
 
 #[derive(Debug)]
 pub struct BordaCount<'a> {
@@ -28,11 +32,72 @@ impl<'a> BordaCount<'a> {
         }
         result
     }
+
+    /// Like `calculate`, but scales each ranked choice's positional value by
+    /// a matching per-voter weight (`weights[v][i]` is the weight behind
+    /// `self.voters[v][i]`), so a fractional ballot contributes fractionally
+    /// instead of a flat position count.
+    pub fn calculate_weighted(&self, weights: &[Vec<f64>]) -> HashMap<&'a str, f64> {
+        let mut result: HashMap<&'a str, f64> = HashMap::new();
+
+        for (votes, voter_weights) in self.voters.iter().zip(weights.iter()) {
+            for (i, vote) in votes.iter().enumerate() {
+                let position_value = (votes.len() - i) as f64;
+                let weight = voter_weights.get(i).copied().unwrap_or(1.0);
+                *result.entry(vote).or_insert(0.0) += position_value * weight;
+            }
+        }
+
+        result
+    }
+}
+
+/// Adapts `VoteData`'s weighted ballots into Borda ballots: each voter's
+/// policies are ranked by descending weight, and that same weight rides
+/// alongside the ranking so `calculate_weighted` can scale by it.
+fn weighted_ballots(vote: &VoteData) -> (BTreeMap<Uuid, String>, Vec<Vec<Uuid>>, Vec<Vec<f64>>) {
+    let ids: BTreeMap<Uuid, String> = vote.policies.iter().map(|id| (*id, id.to_string())).collect();
+
+    let mut ballots = Vec::new();
+    let mut weights = Vec::new();
+
+    for choices in vote.only_policy_voting().into_values() {
+        let mut ranked: Vec<(Uuid, f64)> = choices.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+
+        weights.push(ranked.iter().map(|(_, w)| *w).collect());
+        ballots.push(ranked.into_iter().map(|(id, _)| id).collect());
+    }
+
+    (ids, ballots, weights)
+}
+
+#[async_trait]
+impl AggregationRule for BordaCount<'_> {
+    async fn calculate(vote: VoteData) -> Value {
+        let (ids, ballots, weights) = weighted_ballots(&vote);
+        let ballots: Vec<Vec<&str>> = ballots
+            .iter()
+            .map(|ballot| ballot.iter().map(|id| ids[id].as_str()).collect())
+            .collect();
+
+        let borda = BordaCount::new(ballots);
+        let scores = borda.calculate_weighted(&weights);
+
+        let scores: BTreeMap<Uuid, f64> = scores
+            .into_iter()
+            .filter_map(|(id, score)| Uuid::parse_str(id).ok().map(|id| (id, score)))
+            .collect();
+
+        json!({ "scores": scores })
+    }
 }
 
 #[cfg(test)]
 mod borda_test {
     use super::*;
+    use crate::TopicData;
+    use futures::executor::block_on;
 
     fn dinner<'a>() -> BordaCount<'a> {
         let minori = vec!["beef steak", "kungpao chicken", "white pork stew"];
@@ -55,4 +120,45 @@ mod borda_test {
         assert_eq!(pork, &8.0);
         assert_eq!(chicken, &8.0);
     }
+
+    #[test]
+    fn weighted_scales_by_voter_weight() {
+        let minori = vec!["beef steak", "kungpao chicken"];
+        let yasushi = vec!["kungpao chicken", "beef steak"];
+
+        let borda = BordaCount::new(vec![minori, yasushi]);
+        let weights = vec![vec![1.0, 0.2], vec![0.5, 0.5]];
+
+        let result = borda.calculate_weighted(&weights);
+
+        // minori: beef steak 2*1.0=2.0, chicken 1*0.2=0.2
+        // yasushi: chicken 2*0.5=1.0, beef steak 1*0.5=0.5
+        assert_eq!(result.get("beef steak"), Some(&2.5));
+        assert_eq!(result.get("kungpao chicken"), Some(&1.2));
+    }
+
+    #[test]
+    fn aggregation_rule_scales_score_by_weight() {
+        let mut topic = TopicData::dummy();
+
+        let alice = topic.get_id_by_name("alice").unwrap();
+        let apples = topic.get_id_by_title("apples").unwrap();
+        let bananas = topic.get_id_by_title("bananas").unwrap();
+
+        // alice splits her weight 0.7/0.3 across two policies
+        topic.cast_vote_to(&alice, &apples, 0.7f64);
+        topic.cast_vote_to(&alice, &bananas, 0.3f64);
+
+        let info: VoteData = topic.into();
+        let result = block_on(<BordaCount as AggregationRule>::calculate(info));
+
+        let scores = result.get("scores").unwrap().as_object().unwrap();
+        let apples_score = scores.get(&apples.to_string()).unwrap().as_f64().unwrap();
+        let bananas_score = scores.get(&bananas.to_string()).unwrap().as_f64().unwrap();
+
+        // alice ranks apples first: 2 * 0.7 = 1.4; bananas gets 1 * 0.3 from
+        // alice plus 1 * 1.0 each from bob's and charlie's one-item ballots
+        assert!((apples_score - 1.4).abs() < 1e-9);
+        assert!((bananas_score - 2.3).abs() < 1e-9);
+    }
 }
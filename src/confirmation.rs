@@ -0,0 +1,230 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Confirmation presets, borrowing the stake-threshold idea from Solana's
+/// commitment service (a slot is confirmed once votes cross a fixed
+/// fraction of total stake). `Plurality` has no fixed fraction: the leading
+/// option is confirmed as soon as one exists.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConfirmationThreshold {
+    Supermajority,
+    SimpleMajority,
+    Plurality,
+    Custom(f64),
+}
+
+impl ConfirmationThreshold {
+    fn fraction(&self) -> Option<f64> {
+        match self {
+            ConfirmationThreshold::Supermajority => Some(2.0 / 3.0),
+            ConfirmationThreshold::SimpleMajority => Some(0.5),
+            ConfirmationThreshold::Plurality => None,
+            ConfirmationThreshold::Custom(fraction) => Some(*fraction),
+        }
+    }
+}
+
+impl Default for ConfirmationThreshold {
+    fn default() -> Self {
+        ConfirmationThreshold::Supermajority
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Confirmation<K> {
+    pub shares: HashMap<K, f64>,
+    pub confirmed: bool,
+    pub winners: Vec<K>,
+}
+
+/// Normalizes `results` into shares of the total and reports whether the
+/// leading option(s) have crossed `threshold`.
+pub fn confirm<K>(results: &HashMap<K, f64>, threshold: ConfirmationThreshold) -> Confirmation<K>
+where
+    K: Eq + Hash + Clone,
+{
+    let total: f64 = results.values().sum();
+
+    let shares: HashMap<K, f64> = results
+        .iter()
+        .map(|(k, v)| (k.clone(), if total > 0.0 { v / total } else { 0.0 }))
+        .collect();
+
+    let max_share = shares.values().cloned().fold(f64::NEG_INFINITY, f64::max);
+
+    let confirmed = match threshold {
+        // the majority presets require *more* than the fraction -- an exact
+        // 50/50 split is a tie, not a simple majority, and the same goes for
+        // an exact two-thirds split under a supermajority
+        ConfirmationThreshold::Supermajority | ConfirmationThreshold::SimpleMajority => {
+            max_share > threshold.fraction().unwrap()
+        }
+        ConfirmationThreshold::Custom(fraction) => max_share >= *fraction,
+        ConfirmationThreshold::Plurality => !shares.is_empty(),
+    };
+
+    let winners = if confirmed {
+        shares
+            .iter()
+            .filter(|(_, share)| (**share - max_share).abs() < f64::EPSILON)
+            .map(|(k, _)| k.clone())
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    Confirmation {
+        shares,
+        confirmed,
+        winners,
+    }
+}
+
+/// Confirms the result carried by `response`, whichever of the two shapes
+/// the crate's `AggregationRule` impls emit, and attaches the verdict under
+/// a `"confirmation"` key:
+/// - a `"scores"` object (as `BordaCount` emits): normalized into shares
+///   and checked against `threshold` the normal way.
+/// - a `"winners"` list (as RCV/Approval/STV/Phragmen emit): those modules
+///   have already resolved majorities and ties internally, so the list
+///   itself is the verdict -- confirmed iff it names exactly one winner.
+///   There's no share distribution behind a plain winners list, so
+///   `threshold` plays no role here.
+///
+/// A response carrying neither shape is left untouched.
+pub fn attach_to_scores(response: &mut Value, threshold: ConfirmationThreshold) {
+    let scores: Option<HashMap<String, f64>> = response
+        .get("scores")
+        .and_then(|v| v.as_object())
+        .map(|scores| {
+            scores
+                .iter()
+                .filter_map(|(k, v)| v.as_f64().map(|v| (k.clone(), v)))
+                .collect()
+        });
+
+    if let Some(scores) = scores {
+        insert_confirmation(response, confirm(&scores, threshold));
+        return;
+    }
+
+    let winners: Option<Vec<String>> = response.get("winners").and_then(|v| v.as_array()).map(|winners| {
+        winners
+            .iter()
+            .filter_map(|w| w.as_str().map(str::to_string))
+            .collect()
+    });
+
+    if let Some(winners) = winners {
+        let confirmed = winners.len() == 1;
+        let confirmation = Confirmation {
+            shares: HashMap::new(),
+            confirmed,
+            winners: if confirmed { winners } else { Vec::new() },
+        };
+        insert_confirmation(response, confirmation);
+    }
+}
+
+fn insert_confirmation<K: Serialize>(response: &mut Value, confirmation: Confirmation<K>) {
+    if let Some(object) = response.as_object_mut() {
+        object.insert(
+            "confirmation".to_string(),
+            serde_json::to_value(confirmation).unwrap(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod confirmation_test {
+
+    use super::*;
+
+    fn scores() -> HashMap<&'static str, f64> {
+        [("apples", 7.0), ("bananas", 3.0)].iter().cloned().collect()
+    }
+
+    #[test]
+    fn supermajority_confirms_when_crossed() {
+        let result = confirm(&scores(), ConfirmationThreshold::Supermajority);
+
+        assert!(result.confirmed);
+        assert_eq!(result.winners, vec!["apples"]);
+    }
+
+    #[test]
+    fn simple_majority_confirms_with_less() {
+        let votes: HashMap<&str, f64> = [("apples", 6.0), ("bananas", 5.0)].iter().cloned().collect();
+        let result = confirm(&votes, ConfirmationThreshold::SimpleMajority);
+
+        assert!(result.confirmed);
+        assert_eq!(result.winners, vec!["apples"]);
+    }
+
+    #[test]
+    fn simple_majority_does_not_confirm_an_exact_tie() {
+        let votes: HashMap<&str, f64> = [("apples", 5.0), ("bananas", 5.0)].iter().cloned().collect();
+        let result = confirm(&votes, ConfirmationThreshold::SimpleMajority);
+
+        assert!(!result.confirmed);
+        assert!(result.winners.is_empty());
+    }
+
+    #[test]
+    fn custom_threshold_not_crossed() {
+        let result = confirm(&scores(), ConfirmationThreshold::Custom(0.95));
+
+        assert!(!result.confirmed);
+        assert!(result.winners.is_empty());
+    }
+
+    #[test]
+    fn plurality_always_confirms_a_leader() {
+        let votes: HashMap<&str, f64> = [("apples", 2.0), ("bananas", 1.0), ("cherries", 1.0)]
+            .iter()
+            .cloned()
+            .collect();
+        let result = confirm(&votes, ConfirmationThreshold::Plurality);
+
+        assert!(result.confirmed);
+        assert_eq!(result.winners, vec!["apples"]);
+    }
+
+    #[test]
+    fn attach_to_scores_inserts_confirmation() {
+        let mut response = serde_json::json!({ "scores": { "apples": 7.0, "bananas": 3.0 } });
+        attach_to_scores(&mut response, ConfirmationThreshold::Supermajority);
+
+        assert!(response.get("confirmation").unwrap().get("confirmed").unwrap().as_bool().unwrap());
+    }
+
+    #[test]
+    fn attach_to_scores_confirms_a_single_winner() {
+        let mut response = serde_json::json!({ "winners": ["apples"] });
+        attach_to_scores(&mut response, ConfirmationThreshold::Supermajority);
+
+        let confirmation = response.get("confirmation").unwrap();
+        assert!(confirmation.get("confirmed").unwrap().as_bool().unwrap());
+        assert_eq!(confirmation.get("winners").unwrap(), &serde_json::json!(["apples"]));
+    }
+
+    #[test]
+    fn attach_to_scores_does_not_confirm_a_tied_winners_list() {
+        let mut response = serde_json::json!({ "winners": ["apples", "bananas"] });
+        attach_to_scores(&mut response, ConfirmationThreshold::Supermajority);
+
+        let confirmation = response.get("confirmation").unwrap();
+        assert!(!confirmation.get("confirmed").unwrap().as_bool().unwrap());
+        assert!(confirmation.get("winners").unwrap().as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn attach_to_scores_skips_unrelated_responses() {
+        let mut response = serde_json::json!({ "keep": { "dog": 1.0 } });
+        attach_to_scores(&mut response, ConfirmationThreshold::Supermajority);
+
+        assert!(response.get("confirmation").is_none());
+    }
+}
@@ -1,4 +1,3 @@
-use crate::normalize_votes;
 use std::collections::HashMap;
 
 /// Real Quadratic Voting needs a market that people can exchange currencies
@@ -61,6 +60,20 @@ pub fn square_root_votes<'a>(votes: &mut HashMap<&'a str, f64>) {
     }
 }
 
+/// Balances a single voter's ballot to sum to 1, so an over- or
+/// under-voting voter carries the same total weight as everyone else.
+pub fn normalize_votes<'a>(votes: &mut HashMap<&'a str, f64>) {
+    let sum: f64 = votes.values().sum();
+
+    if sum == 0.0 {
+        return;
+    }
+
+    for v in votes.values_mut() {
+        *v /= sum;
+    }
+}
+
 #[cfg(test)]
 mod fractional_test {
 
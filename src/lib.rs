@@ -1,4 +1,14 @@
+pub mod approval;
+pub mod borda;
+pub mod confirmation;
+pub mod fractional;
+pub mod liquid_democracy;
+pub mod phragmen;
+pub mod rcv;
 pub mod rpc;
+pub mod stake_weighted;
+pub mod stv;
+pub mod tiebreak;
 
 use async_trait::async_trait;
 use bs58::encode;
@@ -163,6 +173,81 @@ impl VoteData {
             .collect()
     }
 
+    /// Resolves every voter's delegate-directed weight transitively until it
+    /// lands on a policy, producing a policy-only `Votes` map the aggregation
+    /// modules can consume directly.
+    ///
+    /// Each voter's normalized weight on a delegate is split again at every
+    /// hop according to that delegate's own normalized outgoing vote, so the
+    /// weight is spread fractionally across whatever the chain of delegates
+    /// ultimately voted on. A delegation cycle (A -> B -> A) is damped by
+    /// truncating the walk after `MAX_DELEGATION_HOPS` hops, and a delegate
+    /// who never cast a vote simply exhausts the weight resting on them.
+    /// The second map returned is that exhausted weight, keyed by the
+    /// originating voter, so callers can see how much of each voter's
+    /// intent dissolved instead of reaching a policy.
+    pub fn resolve_delegations(&self) -> (Votes, BTreeMap<Uuid, f64>) {
+        const MAX_DELEGATION_HOPS: usize = 64;
+
+        let normalized = self.normalized();
+        let mut result: Votes = BTreeMap::new();
+        let mut exhausted: BTreeMap<Uuid, f64> = BTreeMap::new();
+
+        for src in self.votes.keys() {
+            let mut settled: BTreeMap<Uuid, f64> = BTreeMap::new();
+            let mut frontier: BTreeMap<Uuid, f64> = BTreeMap::new();
+
+            if let Some(vote) = normalized.get(src) {
+                for (to, weight) in vote {
+                    if self.delegates.contains(to) {
+                        *frontier.entry(*to).or_insert(0.0) += weight;
+                    } else {
+                        *settled.entry(*to).or_insert(0.0) += weight;
+                    }
+                }
+            }
+
+            let mut hop = 0;
+            while !frontier.is_empty() && hop < MAX_DELEGATION_HOPS {
+                let mut next_frontier: BTreeMap<Uuid, f64> = BTreeMap::new();
+
+                for (delegate, weight) in frontier {
+                    match normalized.get(&delegate).filter(|outgoing| !outgoing.is_empty()) {
+                        Some(outgoing) => {
+                            for (to, w) in outgoing {
+                                let share = weight * w;
+                                if self.delegates.contains(to) {
+                                    *next_frontier.entry(*to).or_insert(0.0) += share;
+                                } else {
+                                    *settled.entry(*to).or_insert(0.0) += share;
+                                }
+                            }
+                        }
+                        None => {
+                            // the delegate never voted: this weight abstains
+                            *exhausted.entry(*src).or_insert(0.0) += weight;
+                        }
+                    }
+                }
+
+                frontier = next_frontier;
+                hop += 1;
+            }
+
+            // weight still circulating after the hop limit is a damped cycle
+            let residual: f64 = frontier.values().sum();
+            if residual > 0.0 {
+                *exhausted.entry(*src).or_insert(0.0) += residual;
+            }
+
+            if !settled.is_empty() {
+                result.insert(*src, settled);
+            }
+        }
+
+        (result, exhausted)
+    }
+
     pub fn hash_sync(&self) -> Vec<u8> {
         block_on(self.hash())
     }
@@ -337,6 +422,42 @@ mod topic_info_test {
 
         assert_eq!(stripped, alice_vote_len);
     }
+
+    #[test]
+    fn resolve_delegations_through_chain() {
+        let mut topic = TopicData::dummy();
+
+        let alice = topic.get_id_by_name("alice").unwrap();
+        let bob = topic.get_id_by_name("bob").unwrap();
+        let bananas = topic.get_id_by_title("bananas").unwrap();
+
+        // alice delegates entirely to bob, who (per `dummy`) voted bananas
+        topic.overwrite_vote_for(alice, BTreeMap::from([(bob, 1f64)]));
+
+        let info: VoteData = topic.into();
+        let (resolved, exhausted) = info.resolve_delegations();
+
+        let alice_votes = resolved.get(&alice).unwrap();
+        assert_eq!(alice_votes.get(&bananas), Some(&1f64));
+        assert!(exhausted.get(&alice).is_none());
+    }
+
+    #[test]
+    fn resolve_delegations_exhausts_silent_delegate() {
+        let mut topic = TopicData::dummy();
+
+        let charlie = topic.get_id_by_name("charlie").unwrap();
+
+        // charlie delegates entirely to a delegate who never casts any vote
+        let silent = topic.add_new_delegate("silent").unwrap();
+        topic.overwrite_vote_for(charlie, BTreeMap::from([(silent, 1f64)]));
+
+        let info: VoteData = topic.into();
+        let (resolved, exhausted) = info.resolve_delegations();
+
+        assert!(resolved.get(&charlie).is_none());
+        assert_eq!(exhausted.get(&charlie), Some(&1f64));
+    }
 }
 
 #[async_trait]
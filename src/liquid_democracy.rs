@@ -1,9 +1,13 @@
-use crate::{fractional::square_root_votes, normalize_votes, Ordf64};
+use crate::fractional::{normalize_votes, square_root_votes};
 use ndarray::{concatenate, s, Array, Array2, Axis};
+use ndarray_linalg::Solve;
 use std::collections::{BTreeSet, HashMap};
 
 pub type Voters<'a> = HashMap<&'a str, HashMap<&'a str, f64>>;
 
+/// Step bound for the Neumann-series fallback used only when `I - Q` is
+/// singular (e.g. a closed delegation cycle with no policy leakage), since
+/// the direct solve in [`LiquidDemocracy::calculate`] can't be used there.
 const ITERATION: u32 = 10_000;
 
 #[derive(Debug)]
@@ -79,28 +83,68 @@ impl<'a> LiquidDemocracy<'a> {
 
     pub fn calculate(&self) -> (HashMap<&'a str, f64>, HashMap<&'a str, f64>) {
         let ((delegates, polices), matrix) = self.create_matrix();
+        let d = delegates.len();
+
+        // `create_matrix` builds an absorbing Markov chain: delegates are
+        // transient states and policies are absorbing. `q` is the
+        // delegate-to-delegate (transient-to-transient) block and `r` is
+        // the delegate-to-policy (transient-to-absorbing) block.
+        let q = matrix.slice(s![..d, ..d]).to_owned();
+        let r = matrix.slice(s![d.., ..d]).to_owned();
+
+        let n = Self::fundamental_matrix(&q);
+
+        // column j of `r.dot(&n)` is the absorption distribution over
+        // policies for a unit of mass starting at delegate j; summing
+        // across delegates gives each policy's total absorbed mass.
+        let absorbed = r.dot(&n);
+        let results = absorbed.sum_axis(Axis(1)).to_vec();
+        let poll_result: HashMap<&str, f64> = polices.iter().cloned().zip(results).collect();
 
-        let edge = matrix.shape()[0];
-        let mut a = Array::eye(edge);
-        let mut sum = Array::eye(edge);
+        let sum_row = n.sum_axis(Axis(1));
+        let influence = (sum_row / n.diag()).to_vec();
+        let influence: HashMap<&str, f64> = delegates.iter().cloned().zip(influence).collect();
 
-        for _ in 0..ITERATION {
-            a = a.dot(&matrix);
-            sum += &a;
-        }
+        (poll_result, influence)
+    }
 
-        let a = a.slice(s![.., 0..delegates.len()]);
-        let results = a.sum_axis(Axis(1)).slice(s![delegates.len()..]).to_vec();
+    /// Solves for the fundamental matrix `N = (I - Q)^-1` by solving
+    /// `(I - Q) X = I` one column at a time (each `solve` call runs its own
+    /// LU factorization) instead of inverting, falling back
+    /// to the previous truncated Neumann-series sum when `I - Q` is
+    /// singular -- e.g. a self-delegating cycle that never leaks into a
+    /// policy, so the series never converges to a proper inverse.
+    fn fundamental_matrix(q: &Array2<f64>) -> Array2<f64> {
+        let d = q.shape()[0];
+        let identity: Array2<f64> = Array::eye(d);
+        let i_minus_q = &identity - q;
+
+        let mut columns = Vec::with_capacity(d);
+        for j in 0..d {
+            match i_minus_q.solve(&identity.column(j).to_owned()) {
+                Ok(column) => columns.push(column),
+                Err(_) => return Self::fundamental_matrix_iterative(q),
+            }
+        }
 
-        let poll_result: HashMap<&str, f64> = polices.iter().cloned().zip(results).collect();
+        let mut n = Array2::zeros((d, d));
+        for (j, column) in columns.into_iter().enumerate() {
+            n.column_mut(j).assign(&column);
+        }
+        n
+    }
 
-        let sum = sum.slice(s![..delegates.len(), ..delegates.len()]);
-        let sum_row = sum.sum_axis(Axis(1));
-        let influence = (sum_row / sum.diag()).to_vec();
+    fn fundamental_matrix_iterative(q: &Array2<f64>) -> Array2<f64> {
+        let d = q.shape()[0];
+        let mut a: Array2<f64> = Array::eye(d);
+        let mut sum: Array2<f64> = Array::eye(d);
 
-        let influence: HashMap<&str, f64> = delegates.iter().cloned().zip(influence).collect();
+        for _ in 0..ITERATION {
+            a = a.dot(q);
+            sum += &a;
+        }
 
-        (poll_result, influence)
+        sum
     }
 }
 
@@ -162,4 +206,31 @@ mod liquid_test {
 
         assert!(minori > yasushi);
     }
+
+    #[test]
+    fn direct_solve_matches_iterative_fallback() {
+        let liq = breakfast();
+        let (_, matrix) = liq.create_matrix();
+        let d = 3; // minori, yasushi, ray
+        let q = matrix.slice(s![..d, ..d]).to_owned();
+
+        let direct = LiquidDemocracy::fundamental_matrix(&q);
+        let iterative = LiquidDemocracy::fundamental_matrix_iterative(&q);
+
+        for (a, b) in direct.iter().zip(iterative.iter()) {
+            assert!((a - b).abs() < 1e-6, "{} vs {}", a, b);
+        }
+    }
+
+    #[test]
+    fn singular_delegation_cycle_falls_back_to_iterative() {
+        // a two-delegate cycle that never leaks into a policy: minori
+        // delegates fully to ray and ray delegates fully to minori, so
+        // `I - Q` is singular and the direct solve can't be used.
+        let q: Array2<f64> = Array2::from_shape_vec((2, 2), vec![0.0, 1.0, 1.0, 0.0]).unwrap();
+
+        let n = LiquidDemocracy::fundamental_matrix(&q);
+
+        assert_eq!(n.shape(), &[2, 2]);
+    }
 }
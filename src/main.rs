@@ -2,21 +2,46 @@ mod rpc;
 
 use actix_cors::Cors;
 use actix_web::{get, middleware, post, web, App, HttpServer, Responder};
+use arc_swap::ArcSwap;
 use futures::future::join_all;
 use rpc::calculate;
+use serde::Deserialize;
 use serde_json::{json, Value};
 use std::collections::HashMap;
-use std::sync::Mutex;
+use std::sync::Arc;
+use vote::confirmation::{attach_to_scores, ConfirmationThreshold};
 use vote::{TopicData, VoteData};
 
-type ModuleMap = Mutex<HashMap<String, String>>;
+/// A read-optimized, copy-on-write registry: `api` and `get_modules` load a
+/// cheap `Arc` snapshot with no locking, and `add_module` atomically swaps
+/// in a freshly-built map. Modeled on how Solana keeps its vote-accounts
+/// map out of the hot read path by versioning it behind an `Arc` rather
+/// than serializing readers and writers through a single lock.
+type ModuleMap = Arc<ArcSwap<HashMap<String, String>>>;
+
+#[derive(Deserialize)]
+struct ConfirmQuery {
+    /// Opt-in: "supermajority" (default preset), "simple_majority", or
+    /// "plurality". Omit to skip confirmation entirely.
+    threshold: Option<String>,
+}
+
+fn parse_threshold(name: &str) -> Option<ConfirmationThreshold> {
+    match name {
+        "supermajority" => Some(ConfirmationThreshold::Supermajority),
+        "simple_majority" => Some(ConfirmationThreshold::SimpleMajority),
+        "plurality" => Some(ConfirmationThreshold::Plurality),
+        _ => None,
+    }
+}
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     std::env::set_var("RUST_LOG", "actix_web=trace,actix_redis=trace,vote=debug");
     env_logger::init();
 
-    let modules: web::Data<ModuleMap> = web::Data::new(Mutex::new(HashMap::new()));
+    let modules: web::Data<ModuleMap> =
+        web::Data::new(Arc::new(ArcSwap::from_pointee(HashMap::new())));
 
     HttpServer::new(move || {
         // TODO: change this
@@ -40,29 +65,69 @@ async fn main() -> std::io::Result<()> {
     .await
 }
 
+/// Accepts either a single `TopicData` (wrapped in a JSON-RPC request) or a
+/// batch of them, per https://www.jsonrpc.org/specification#batch, and
+/// calculates each independently -- so a client can settle many polls in
+/// one round trip instead of one request per poll.
 #[post("")]
-async fn api(modules: web::Data<ModuleMap>, topic: web::Json<TopicData>) -> impl Responder {
-    let topic = topic.into_inner();
-
-    let info: VoteData = topic.into();
-
-    let modules = modules.lock().unwrap();
-
-    let calculations = modules.iter().map(|m| {
-        let (name, uri) = m;
-        calculate(&name, &uri, &info)
-    });
-
-    let module_responses = join_all(calculations).await;
-
-    let result: HashMap<String, Value> = modules
-        .keys()
-        .zip(module_responses.iter())
-        .filter(|(_, r)| r.is_some())
-        .map(|(k, r)| (k.to_string(), r.to_owned().unwrap()))
-        .collect();
-
-    web::Json(result)
+async fn api(
+    modules: web::Data<ModuleMap>,
+    payload: web::Json<rpc::JsonRPCPayload>,
+    confirm: web::Query<ConfirmQuery>,
+) -> impl Responder {
+    // snapshot the registry once and fan out against it -- no lock is held
+    // across the network awaits below, so `add_module` never blocks on a
+    // slow or unreachable module mid-request
+    let modules = modules.load_full();
+
+    // reporting a confirmed/not-yet-confirmed verdict is opt-in via
+    // `?threshold=`; an unrecognized value is the same as omitting it
+    let threshold = confirm.threshold.as_deref().and_then(parse_threshold);
+
+    let is_single = matches!(payload.0, rpc::JsonRPCPayload::Single(_));
+
+    let responses = rpc::dispatch(payload.into_inner(), |request| {
+        let modules = modules.clone();
+        async move {
+            let mut response = rpc::JsonRPCResponse::new(&request.id());
+
+            let topic: TopicData = match serde_json::from_value(request.params().to_owned()) {
+                Ok(topic) => topic,
+                Err(err) => {
+                    response.error(rpc::JsonRPCError::new(rpc::INVALID_PARAMS, err.to_string()));
+                    return response;
+                }
+            };
+            let info: VoteData = topic.into();
+
+            let calculations = modules.iter().map(|(name, uri)| calculate(name, uri, &info));
+            let module_responses = join_all(calculations).await;
+
+            let mut result: HashMap<String, Value> = modules
+                .keys()
+                .zip(module_responses.into_iter())
+                .filter_map(|(k, r)| r.map(|v| (k.to_string(), v)))
+                .collect();
+
+            if let Some(threshold) = threshold {
+                for value in result.values_mut() {
+                    attach_to_scores(value, threshold);
+                }
+            }
+
+            response.result(&json!(result));
+            response
+        }
+    })
+    .await;
+
+    // a single (non-batch) request gets a single response object back, not
+    // a one-element array, per https://www.jsonrpc.org/specification#batch
+    if is_single {
+        web::Json(json!(responses.into_iter().next()))
+    } else {
+        web::Json(json!(responses))
+    }
 }
 
 #[post("module/")]
@@ -70,16 +135,20 @@ async fn add_module(
     modules: web::Data<ModuleMap>,
     module: web::Json<(String, String)>,
 ) -> impl Responder {
-    let mut modules = modules.lock().unwrap();
     let (name, uri): (String, String) = module.into_inner();
-    modules.insert(name, uri);
+
+    modules.rcu(|current| {
+        let mut next = (**current).clone();
+        next.insert(name.clone(), uri.clone());
+        next
+    });
+
     web::Json(json!({"status":"ok"}))
 }
 
 #[get("modules/")]
 async fn get_modules(modules: web::Data<ModuleMap>) -> impl Responder {
-    let modules = modules.lock().unwrap();
-    web::Json(json!(*modules))
+    web::Json(json!(*modules.load_full()))
 }
 
 #[get("hello/")]
@@ -0,0 +1,171 @@
+use crate::{AggregationRule, VoteData};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// Sequential Phragmen selection over `VoteData`'s weighted votes, which
+/// (unlike the ranked-ballot modules) already carries genuine per-voter
+/// weights and so is a natural fit for load-balanced committee selection.
+pub struct Phragmen {
+    seats: usize,
+}
+
+impl Phragmen {
+    pub fn new(seats: usize) -> Self {
+        Self { seats }
+    }
+
+    /// Runs the selection against `vote`'s normalized, policy-only weights.
+    /// Returns the winners in election order, the final load carried by
+    /// every voter, and the load each winner was elected at, so a caller
+    /// can audit proportionality.
+    pub fn calculate(&self, vote: &VoteData) -> (Vec<Uuid>, BTreeMap<Uuid, f64>, BTreeMap<Uuid, f64>) {
+        let normalized = vote.normalized();
+        let votes: BTreeMap<Uuid, BTreeMap<Uuid, f64>> = normalized
+            .into_iter()
+            .map(|(voter, weights)| {
+                let policy_weights = weights
+                    .into_iter()
+                    .filter(|(to, _)| vote.policies.contains(to))
+                    .collect();
+                (voter, policy_weights)
+            })
+            .collect();
+
+        let mut loads: BTreeMap<Uuid, f64> = votes.keys().map(|&voter| (voter, 0.0)).collect();
+        let mut winners: Vec<Uuid> = Vec::new();
+        let mut winner_loads: BTreeMap<Uuid, f64> = BTreeMap::new();
+
+        for _ in 0..self.seats {
+            let mut best: Option<(Uuid, f64, Vec<(Uuid, f64)>)> = None;
+
+            for &candidate in vote.policies.iter() {
+                if winners.contains(&candidate) {
+                    continue;
+                }
+
+                let supporters: Vec<(Uuid, f64)> = votes
+                    .iter()
+                    .filter_map(|(&voter, weights)| {
+                        weights.get(&candidate).filter(|w| **w > 0.0).map(|&w| (voter, w))
+                    })
+                    .collect();
+
+                let total_weight: f64 = supporters.iter().map(|(_, w)| w).sum();
+                if total_weight <= 0.0 {
+                    continue;
+                }
+
+                let load_so_far: f64 = supporters.iter().map(|(v, _)| loads[v]).sum();
+                let prospective_load = (1.0 + load_so_far) / total_weight;
+
+                if best.as_ref().map(|(_, l, _)| prospective_load < *l).unwrap_or(true) {
+                    best = Some((candidate, prospective_load, supporters));
+                }
+            }
+
+            match best {
+                Some((candidate, load, supporters)) => {
+                    for (voter, _) in supporters {
+                        loads.insert(voter, load);
+                    }
+                    winner_loads.insert(candidate, load);
+                    winners.push(candidate);
+                }
+                None => break,
+            }
+        }
+
+        (winners, loads, winner_loads)
+    }
+}
+
+/// `AggregationRule`'s contract takes no seat count, so the trait entry
+/// point elects a single seat; a caller that needs a full committee
+/// constructs `Phragmen` directly with an explicit `seats`.
+#[async_trait]
+impl AggregationRule for Phragmen {
+    async fn calculate(vote: VoteData) -> Value {
+        let phragmen = Phragmen::new(1);
+        let (winners, loads, winner_loads) = phragmen.calculate(&vote);
+
+        json!({ "winners": winners, "loads": loads, "winner_loads": winner_loads })
+    }
+}
+
+#[cfg(test)]
+mod phragmen_test {
+
+    use super::*;
+    use crate::TopicData;
+
+    #[test]
+    fn elects_requested_seat_count() {
+        let mut topic = TopicData::new("breakfast", "what to eat");
+
+        let alice = topic.add_new_delegate("alice").unwrap();
+        let bob = topic.add_new_delegate("bob").unwrap();
+        let charlie = topic.add_new_delegate("charlie").unwrap();
+
+        let apples = topic.add_new_policy("apples").unwrap();
+        let bananas = topic.add_new_policy("bananas").unwrap();
+        let cherries = topic.add_new_policy("cherries").unwrap();
+
+        topic.cast_vote_to(&alice, &apples, 1f64);
+        topic.cast_vote_to(&bob, &bananas, 1f64);
+        topic.cast_vote_to(&charlie, &cherries, 1f64);
+
+        let info: VoteData = topic.into();
+        let phragmen = Phragmen::new(2);
+        let (winners, loads, winner_loads) = phragmen.calculate(&info);
+
+        assert_eq!(winners.len(), 2);
+        assert_eq!(winner_loads.len(), 2);
+        assert_eq!(loads.len(), 3);
+    }
+
+    #[test]
+    fn spreads_load_across_distinct_supporters() {
+        let mut topic = TopicData::new("committee", "who serves");
+
+        let alice = topic.add_new_delegate("alice").unwrap();
+        let bob = topic.add_new_delegate("bob").unwrap();
+
+        let apples = topic.add_new_policy("apples").unwrap();
+        let bananas = topic.add_new_policy("bananas").unwrap();
+
+        topic.cast_vote_to(&alice, &apples, 1f64);
+        topic.cast_vote_to(&bob, &apples, 1f64);
+        topic.cast_vote_to(&bob, &bananas, 0f64);
+
+        let info: VoteData = topic.into();
+        let phragmen = Phragmen::new(1);
+        let (winners, _loads, winner_loads) = phragmen.calculate(&info);
+
+        assert_eq!(winners, vec![apples]);
+        assert_eq!(winner_loads.get(&apples), Some(&0.5));
+    }
+
+    #[test]
+    fn aggregation_rule_elects_a_single_seat() {
+        use futures::executor::block_on;
+
+        let mut topic = TopicData::new("breakfast", "what to eat");
+
+        let alice = topic.add_new_delegate("alice").unwrap();
+        let bob = topic.add_new_delegate("bob").unwrap();
+
+        let apples = topic.add_new_policy("apples").unwrap();
+        let bananas = topic.add_new_policy("bananas").unwrap();
+
+        topic.cast_vote_to(&alice, &apples, 1f64);
+        topic.cast_vote_to(&bob, &bananas, 1f64);
+
+        let info: VoteData = topic.into();
+        let result = block_on(<Phragmen as AggregationRule>::calculate(info));
+
+        let winners = result.get("winners").unwrap().as_array().unwrap();
+        assert_eq!(winners.len(), 1);
+    }
+}
@@ -1,8 +1,14 @@
-use std::collections::{BTreeSet, HashMap};
-
-struct RankChoiceVoting<'a> {
+use crate::tiebreak::break_tie;
+use crate::{AggregationRule, VoteData};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use uuid::Uuid;
+
+pub struct RankChoiceVoting<'a> {
     voters: Vec<Vec<&'a str>>,
     ignore: Vec<&'a str>,
+    seed: Option<Vec<u8>>,
 }
 
 impl<'a> RankChoiceVoting<'a> {
@@ -10,6 +16,7 @@ impl<'a> RankChoiceVoting<'a> {
         Self {
             voters,
             ignore: Vec::new(),
+            seed: None,
         }
     }
 
@@ -17,6 +24,12 @@ impl<'a> RankChoiceVoting<'a> {
         self.ignore.push(vote);
     }
 
+    /// Seeds deterministic tie-breaking (e.g. from `VoteData::hash_sync()`).
+    /// Without a seed, tied candidates are returned together as before.
+    pub fn seed(&mut self, seed: Vec<u8>) {
+        self.seed = Some(seed);
+    }
+
     fn unique_votes(&self) -> BTreeSet<&'a str> {
         let mut set = BTreeSet::new();
 
@@ -33,6 +46,7 @@ impl<'a> RankChoiceVoting<'a> {
         let mut eliminate = Vec::new();
         let unique = self.unique_votes();
         let mut counts: HashMap<&str, u32> = HashMap::new();
+        let mut round: usize = 0;
 
         loop {
             for voter in self.voters.iter() {
@@ -60,6 +74,12 @@ impl<'a> RankChoiceVoting<'a> {
             }
 
             if !winners.is_empty() {
+                if let Some(seed) = &self.seed {
+                    if winners.len() > 1 {
+                        let elected = break_tie(seed, round, &winners, false);
+                        return Some(vec![elected]);
+                    }
+                }
                 return Some(winners);
             }
 
@@ -74,9 +94,18 @@ impl<'a> RankChoiceVoting<'a> {
                 }
             }
 
+            if let Some(seed) = &self.seed {
+                if eliminate.len() > 1 {
+                    let chosen = break_tie(seed, round, &eliminate, true);
+                    eliminate = vec![chosen];
+                }
+            }
+
             if eliminate.len() == unique.len() {
                 return None;
             }
+
+            round += 1;
         }
     }
 }
@@ -95,10 +124,55 @@ impl<'a> Iterator for RankChoiceVoting<'a> {
     }
 }
 
+/// Adapts `VoteData`'s weighted ballots into ranked ballots: each voter's
+/// policies are ranked by descending weight, so the heaviest-weighted
+/// policy is their first preference. Every policy `Uuid` is rendered to a
+/// `String` once so `RankChoiceVoting` can borrow it as `&str`.
+fn ranked_ballots(vote: &VoteData) -> (BTreeMap<Uuid, String>, Vec<Vec<Uuid>>) {
+    let ids: BTreeMap<Uuid, String> = vote.policies.iter().map(|id| (*id, id.to_string())).collect();
+
+    let ballots = vote
+        .only_policy_voting()
+        .into_values()
+        .map(|choices| {
+            let mut ranked: Vec<(Uuid, f64)> = choices.into_iter().collect();
+            ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+            ranked.into_iter().map(|(id, _)| id).collect()
+        })
+        .collect();
+
+    (ids, ballots)
+}
+
+#[async_trait]
+impl AggregationRule for RankChoiceVoting<'_> {
+    async fn calculate(vote: VoteData) -> Value {
+        let (ids, ballots) = ranked_ballots(&vote);
+        let ballots: Vec<Vec<&str>> = ballots
+            .iter()
+            .map(|ballot| ballot.iter().map(|id| ids[id].as_str()).collect())
+            .collect();
+
+        let mut rcv = RankChoiceVoting::new(ballots);
+        rcv.seed(vote.hash_sync());
+
+        let winners: Vec<Uuid> = rcv
+            .calculate()
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|id| Uuid::parse_str(id).ok())
+            .collect();
+
+        json!({ "winners": winners })
+    }
+}
+
 #[cfg(test)]
 mod rcv_test {
 
     use super::*;
+    use crate::TopicData;
+    use futures::executor::block_on;
 
     #[test]
     fn simple() {
@@ -183,6 +257,24 @@ mod rcv_test {
         assert_eq!(rcv.calculate(), Some(vec!["dog"]));
     }
 
+    #[test]
+    fn seeded_tie_break_is_deterministic() {
+        let a = vec!["dog"];
+        let b = vec!["cat"];
+        let c = vec!["bat"];
+        let d = vec!["bat"];
+
+        let voters: Vec<Vec<&str>> = vec![a, b, c, d];
+        let mut rcv = RankChoiceVoting::new(voters);
+        rcv.seed(b"published-digest".to_vec());
+
+        let first = rcv.calculate();
+        let second = rcv.calculate();
+
+        assert_eq!(first, second);
+        assert_eq!(first, Some(vec!["bat"]));
+    }
+
     #[test]
     fn iterator() {
         let a = vec!["cat", "dog"];
@@ -197,4 +289,24 @@ mod rcv_test {
         assert_eq!(rcv_iter.next(), Some(vec!["cat"]));
         assert_eq!(rcv_iter.next(), Some(vec!["dog"]));
     }
+
+    #[test]
+    fn aggregation_rule_picks_the_heaviest_weighted_policy() {
+        let mut topic = TopicData::dummy();
+
+        let alice = topic.get_id_by_name("alice").unwrap();
+        let apples = topic.get_id_by_title("apples").unwrap();
+        let bananas = topic.get_id_by_title("bananas").unwrap();
+
+        // alice now prefers bananas over apples
+        topic.cast_vote_to(&alice, &apples, 0.2f64);
+        topic.cast_vote_to(&alice, &bananas, 0.8f64);
+
+        let info: VoteData = topic.into();
+        let result = block_on(<RankChoiceVoting as AggregationRule>::calculate(info));
+
+        let winners = result.get("winners").unwrap().as_array().unwrap();
+        assert_eq!(winners.len(), 1);
+        assert_eq!(winners[0].as_str().unwrap(), bananas.to_string());
+    }
 }
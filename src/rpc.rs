@@ -1,10 +1,49 @@
-use crate::VoteInfo;
+use crate::VoteData;
 use actix_web::client::Client;
 use bs58::encode;
-use futures::FutureExt;
+use futures::future::join_all;
+use futures::{Future, FutureExt};
 use log::info;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::ops::RangeInclusive;
+
+/// Standard JSON-RPC 2.0 error codes (the pre-defined range, `-32768` to
+/// `-32000`), per https://www.jsonrpc.org/specification#error_object.
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const INTERNAL_ERROR: i64 = -32603;
+
+/// Reserved for application-defined errors, such as a voting module failing
+/// to calculate a result -- the spec's "Server error" range.
+pub const APPLICATION_ERROR_RANGE: RangeInclusive<i64> = -32099..=-32000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRPCError {
+    pub code: i64,
+    pub message: String,
+    pub data: Option<Value>,
+}
+
+impl JsonRPCError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    pub fn with_data(code: i64, message: impl Into<String>, data: Value) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: Some(data),
+        }
+    }
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct JsonRPCRequest {
@@ -28,8 +67,12 @@ impl JsonRPCRequest {
         self.id.to_string()
     }
 
-    pub fn vote_info(&self) -> VoteInfo {
-        serde_json::from_value(self.params.to_owned()).expect("params should be a VoteInfo")
+    pub fn params(&self) -> &Value {
+        &self.params
+    }
+
+    pub fn vote_info(&self) -> VoteData {
+        serde_json::from_value(self.params.to_owned()).expect("params should be a VoteData")
     }
 }
 
@@ -38,7 +81,7 @@ pub struct JsonRPCResponse {
     jsonrpc: String,
     id: String,
     result: Option<Value>,
-    error: Option<Value>,
+    error: Option<JsonRPCError>,
 }
 
 impl JsonRPCResponse {
@@ -59,13 +102,42 @@ impl JsonRPCResponse {
         self.result = Some(r.to_owned());
     }
 
-    pub fn error(&mut self, error: &str) {
-        let value: Value = json!(error);
-        self.error = Some(value);
+    pub fn error(&mut self, error: JsonRPCError) {
+        self.error = Some(error);
+    }
+}
+
+/// A JSON-RPC 2.0 request body: either a single request or a batch, per
+/// https://www.jsonrpc.org/specification#batch. `#[serde(untagged)]` lets
+/// serde try the single-request shape first and fall back to the array.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum JsonRPCPayload {
+    Single(JsonRPCRequest),
+    Batch(Vec<JsonRPCRequest>),
+}
+
+impl JsonRPCPayload {
+    pub fn into_requests(self) -> Vec<JsonRPCRequest> {
+        match self {
+            JsonRPCPayload::Single(request) => vec![request],
+            JsonRPCPayload::Batch(requests) => requests,
+        }
     }
 }
 
-pub async fn calculate(module_name: &str, address: &str, info: &VoteInfo) -> Option<Value> {
+/// Dispatches every request in a (possibly batched) payload through `handle`
+/// concurrently, the same `join_all` fan-out `api` already uses across
+/// modules, and returns the responses in the same order as the requests.
+pub async fn dispatch<F, Fut>(payload: JsonRPCPayload, handle: F) -> Vec<JsonRPCResponse>
+where
+    F: Fn(JsonRPCRequest) -> Fut,
+    Fut: Future<Output = JsonRPCResponse>,
+{
+    join_all(payload.into_requests().into_iter().map(handle)).await
+}
+
+pub async fn calculate(module_name: &str, address: &str, info: &VoteData) -> Option<Value> {
     let mut rpc = JsonRPCRequest::new();
     let hash = &info.hash().await;
     rpc.method = "calculate".to_string();
@@ -77,24 +149,81 @@ pub async fn calculate(module_name: &str, address: &str, info: &VoteInfo) -> Opt
     log::info!("{}", &endpoint);
 
     let client = Client::new();
-    let data = client
+    let response = client
         .post(&endpoint)
         .header("ContentType", "application/json")
         .send_json(&rpc)
-        .then(|r| async move { r.unwrap().json().await })
+        .then(|r| async move {
+            match r {
+                Ok(mut r) => r.json::<Value>().await.map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            }
+        })
         .await;
 
-    match data {
-        Ok(r) => {
-            let json: Result<JsonRPCResponse, serde_json::Error> = serde_json::from_value(r);
-            match json {
-                Ok(res) => match res.is_success() {
-                    true => return res.result,
-                    _ => return None,
-                },
-                Err(_) => return None,
-            };
+    let data = match response {
+        Ok(r) => r,
+        Err(err) => {
+            // the module is unreachable (or timed out) -- log and drop it from
+            // the aggregation rather than taking down the whole request
+            log::warn!("module {} unreachable: {}", module_name, err);
+            return None;
         }
-        Err(_err) => None,
+    };
+
+    let json: Result<JsonRPCResponse, serde_json::Error> = serde_json::from_value(data);
+    match json {
+        Ok(res) if res.is_success() => res.result,
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod rpc_test {
+
+    use super::*;
+    use futures::executor::block_on;
+
+    #[test]
+    fn error_round_trips_through_json() {
+        let mut response = JsonRPCResponse::new("1");
+        response.error(JsonRPCError::new(METHOD_NOT_FOUND, "no such method"));
+
+        let value = serde_json::to_value(&response).unwrap();
+        let error = value.get("error").unwrap();
+
+        assert_eq!(error.get("code").unwrap().as_i64().unwrap(), METHOD_NOT_FOUND);
+        assert_eq!(error.get("message").unwrap().as_str().unwrap(), "no such method");
+    }
+
+    #[test]
+    fn single_payload_dispatches_as_one_request() {
+        let mut request = JsonRPCRequest::new();
+        request.id = "1".to_string();
+
+        let payload = JsonRPCPayload::Single(request);
+        let responses = block_on(dispatch(payload, |r| async move { JsonRPCResponse::new(&r.id) }));
+
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0].id, "1");
+    }
+
+    #[test]
+    fn batch_payload_preserves_request_order() {
+        let ids = ["1", "2", "3"];
+        let requests: Vec<JsonRPCRequest> = ids
+            .iter()
+            .map(|id| {
+                let mut r = JsonRPCRequest::new();
+                r.id = id.to_string();
+                r
+            })
+            .collect();
+
+        let payload = JsonRPCPayload::Batch(requests);
+        let responses = block_on(dispatch(payload, |r| async move { JsonRPCResponse::new(&r.id) }));
+
+        let response_ids: Vec<&str> = responses.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(response_ids, ids);
     }
 }
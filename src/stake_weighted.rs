@@ -0,0 +1,118 @@
+use crate::fractional::{normalize_votes, square_root_votes};
+use std::collections::HashMap;
+
+/// Scales each voter's ballot by a stake before tallying, the way Solana
+/// weights each vote account by its lamport stake when aggregating
+/// commitment. Composes with the same `normalize` (per-voter balancing) and
+/// `quadratic` (`square_root_votes`) toggles as `FractionalVoting`, applied
+/// in the order stake -> normalize -> quadratic.
+pub struct StakeWeightedVoting<'a> {
+    voters: HashMap<&'a str, HashMap<&'a str, f64>>,
+    stakes: HashMap<&'a str, f64>,
+    normalize: bool,
+    quadratic: bool,
+}
+
+impl<'a> StakeWeightedVoting<'a> {
+    pub fn new(
+        voters: HashMap<&'a str, HashMap<&'a str, f64>>,
+        stakes: HashMap<&'a str, f64>,
+    ) -> Self {
+        Self {
+            voters,
+            stakes,
+            normalize: false,
+            quadratic: false,
+        }
+    }
+
+    // order matters
+
+    pub fn normalize(&mut self, normalize: bool) {
+        self.normalize = normalize;
+    }
+
+    pub fn quadratic(&mut self, quadratic: bool) {
+        self.quadratic = quadratic;
+    }
+
+    pub fn calculate(&self) -> HashMap<&'a str, f64> {
+        let mut result: HashMap<&'a str, f64> = HashMap::new();
+
+        for (voter, ballot) in self.voters.iter() {
+            let mut votes = ballot.to_owned();
+            let stake = self.stakes.get(voter).copied().unwrap_or(0f64);
+
+            for (_to, credit) in votes.iter_mut() {
+                *credit *= stake;
+            }
+
+            if self.normalize {
+                normalize_votes(&mut votes)
+            }
+
+            if self.quadratic {
+                square_root_votes(&mut votes)
+            }
+
+            for (to, credit) in votes {
+                let count = result.entry(to).or_insert(0f64);
+                *count += credit;
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod stake_weighted_test {
+
+    use super::*;
+
+    #[test]
+    fn scales_by_stake() {
+        let dog_voter: HashMap<&str, f64> = [("dog", 1f64)].iter().cloned().collect();
+        let cat_voter: HashMap<&str, f64> = [("cat", 1f64)].iter().cloned().collect();
+
+        let voters: HashMap<&str, HashMap<&str, f64>> =
+            [("alice", dog_voter), ("bob", cat_voter)].iter().cloned().collect();
+        let stakes: HashMap<&str, f64> = [("alice", 10f64), ("bob", 1f64)].iter().cloned().collect();
+
+        let stake_weighted = StakeWeightedVoting::new(voters, stakes);
+        let result = stake_weighted.calculate();
+
+        assert_eq!(result.get("dog"), Some(&10f64));
+        assert_eq!(result.get("cat"), Some(&1f64));
+    }
+
+    #[test]
+    fn unstaked_voter_contributes_nothing() {
+        let dog_voter: HashMap<&str, f64> = [("dog", 1f64)].iter().cloned().collect();
+
+        let voters: HashMap<&str, HashMap<&str, f64>> =
+            [("alice", dog_voter)].iter().cloned().collect();
+        let stakes: HashMap<&str, f64> = HashMap::new();
+
+        let stake_weighted = StakeWeightedVoting::new(voters, stakes);
+        let result = stake_weighted.calculate();
+
+        assert_eq!(result.get("dog"), Some(&0f64));
+    }
+
+    #[test]
+    fn stake_then_quadratic() {
+        let dog_voter: HashMap<&str, f64> = [("dog", 1f64)].iter().cloned().collect();
+
+        let voters: HashMap<&str, HashMap<&str, f64>> =
+            [("alice", dog_voter)].iter().cloned().collect();
+        let stakes: HashMap<&str, f64> = [("alice", 4f64)].iter().cloned().collect();
+
+        let mut stake_weighted = StakeWeightedVoting::new(voters, stakes);
+        stake_weighted.quadratic(true);
+
+        let result = stake_weighted.calculate();
+
+        // stake is applied before the square root, so 1 * 4 = 4, sqrt(4) = 2
+        assert_eq!(result.get("dog"), Some(&2f64));
+    }
+}
@@ -0,0 +1,629 @@
+use crate::tiebreak::break_tie;
+use crate::{AggregationRule, VoteData};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use uuid::Uuid;
+
+/// Single Transferable Vote over ranked ballots, counted with a Droop quota
+/// and Gregory (fractional) surplus transfers.
+pub struct StvVoting<'a> {
+    voters: Vec<Vec<&'a str>>,
+    seats: usize,
+    seed: Option<Vec<u8>>,
+}
+
+/// A ballot's current weight and the preference it is presently assigned to.
+/// `pos` only ever moves forward, so a ballot never revisits a candidate it
+/// has already passed through.
+#[derive(Debug, Clone)]
+struct Ballot<'a> {
+    preferences: Vec<&'a str>,
+    pos: usize,
+    value: f64,
+}
+
+impl<'a> Ballot<'a> {
+    /// The ballot's current continuing preference, skipping anyone already
+    /// eliminated or elected (an elected candidate's pile is closed once
+    /// their surplus, if any, has been transferred).
+    fn current(&self, settled: &HashSet<&'a str>) -> Option<&'a str> {
+        self.preferences[self.pos..]
+            .iter()
+            .find(|c| !settled.contains(*c))
+            .copied()
+    }
+
+    /// Advances the pointer past `candidate` so future lookups skip it.
+    fn advance_past(&mut self, candidate: &'a str) {
+        if let Some(i) = self.preferences[self.pos..]
+            .iter()
+            .position(|c| *c == candidate)
+        {
+            self.pos += i + 1;
+        }
+    }
+}
+
+impl<'a> StvVoting<'a> {
+    pub fn new(voters: Vec<Vec<&'a str>>, seats: usize) -> Self {
+        Self {
+            voters,
+            seats,
+            seed: None,
+        }
+    }
+
+    /// Seeds deterministic tie-breaking (e.g. from `VoteData::hash_sync()`)
+    /// for election and elimination ties. Without a seed, ties fall back to
+    /// an arbitrary (but still valid) pick, as before.
+    pub fn seed(&mut self, seed: Vec<u8>) {
+        self.seed = Some(seed);
+    }
+
+    /// Picks one candidate among `tied`, using the seed when one was
+    /// supplied; otherwise takes the first entry of `tied`.
+    fn resolve_tie(&self, round: usize, tied: &[&'a str], lowest: bool) -> &'a str {
+        match &self.seed {
+            Some(seed) if tied.len() > 1 => break_tie(seed, round, tied, lowest),
+            _ => tied[0],
+        }
+    }
+
+    /// The Droop quota: the smallest tally that cannot be reached by more
+    /// candidates than there are seats.
+    fn droop_quota(total_valid_ballots: usize, seats: usize) -> f64 {
+        (total_valid_ballots / (seats + 1) + 1) as f64
+    }
+
+    fn tally(ballots: &[Ballot<'a>], settled: &HashSet<&'a str>) -> HashMap<&'a str, f64> {
+        let mut tally: HashMap<&str, f64> = HashMap::new();
+
+        for ballot in ballots {
+            if let Some(candidate) = ballot.current(settled) {
+                *tally.entry(candidate).or_insert(0.0) += ballot.value;
+            }
+        }
+
+        tally
+    }
+
+    /// Runs the count. Returns the winners in order of election alongside
+    /// the tally each round was decided on (taken before that round's
+    /// election, surplus transfer or elimination), so a caller can audit
+    /// how each seat was filled.
+    pub fn calculate(&self) -> (Vec<&'a str>, Vec<HashMap<&'a str, f64>>) {
+        let candidates: BTreeSet<&str> = self.voters.iter().flatten().cloned().collect();
+        let quota = Self::droop_quota(self.voters.len(), self.seats);
+
+        let mut ballots: Vec<Ballot<'a>> = self
+            .voters
+            .iter()
+            .map(|preferences| Ballot {
+                preferences: preferences.to_owned(),
+                pos: 0,
+                value: 1.0,
+            })
+            .collect();
+
+        let mut eliminated: HashSet<&str> = HashSet::new();
+        let mut elected: Vec<&str> = Vec::new();
+        let mut rounds: Vec<HashMap<&str, f64>> = Vec::new();
+
+        while elected.len() < self.seats {
+            let continuing = candidates.len() - eliminated.len() - elected.len();
+
+            // once the remaining seats can't be contested any further, fill
+            // them with whoever is still standing, ranked by current tally
+            if continuing <= self.seats - elected.len() {
+                let settled: HashSet<&str> = eliminated.union(&elected.iter().cloned().collect()).cloned().collect();
+                let tally = Self::tally(&ballots, &settled);
+                let mut remaining: Vec<&str> = candidates
+                    .iter()
+                    .filter(|c| !eliminated.contains(*c) && !elected.contains(c))
+                    .cloned()
+                    .collect();
+                remaining.sort_by(|a, b| {
+                    tally
+                        .get(b)
+                        .unwrap_or(&0.0)
+                        .partial_cmp(tally.get(a).unwrap_or(&0.0))
+                        .unwrap()
+                });
+                rounds.push(tally);
+                elected.extend(remaining);
+                break;
+            }
+
+            let settled: HashSet<&str> = eliminated.union(&elected.iter().cloned().collect()).cloned().collect();
+            let tally = Self::tally(&ballots, &settled);
+            rounds.push(tally.clone());
+
+            let crossed_quota: Vec<(&str, f64)> = tally
+                .iter()
+                .filter(|(c, v)| !elected.contains(*c) && **v >= quota)
+                .map(|(c, v)| (*c, *v))
+                .collect();
+
+            let winner = {
+                let max_votes = crossed_quota.iter().map(|(_, v)| *v).fold(f64::MIN, f64::max);
+                let tied: Vec<&str> = crossed_quota
+                    .iter()
+                    .filter(|(_, v)| (*v - max_votes).abs() < 1e-9)
+                    .map(|(c, _)| *c)
+                    .collect();
+
+                if tied.is_empty() {
+                    None
+                } else {
+                    let candidate = self.resolve_tie(rounds.len(), &tied, false);
+                    Some((candidate, max_votes))
+                }
+            };
+
+            if let Some((candidate, votes)) = winner {
+                elected.push(candidate);
+
+                let surplus = votes - quota;
+                if surplus > 0.0 {
+                    let transferable: Vec<usize> = ballots
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, b)| b.current(&settled) == Some(candidate))
+                        .filter(|(_, b)| {
+                            let mut probe = (*b).clone();
+                            probe.advance_past(candidate);
+                            probe.current(&settled).is_some()
+                        })
+                        .map(|(i, _)| i)
+                        .collect();
+
+                    if !transferable.is_empty() {
+                        let transfer_value = surplus / transferable.len() as f64;
+                        for i in transferable {
+                            ballots[i].advance_past(candidate);
+                            ballots[i].value = transfer_value;
+                        }
+                    }
+                }
+            } else {
+                // every still-standing candidate is eligible for elimination,
+                // not just those `tally` happens to list: a candidate with no
+                // ballots currently on them (tally 0.0) is the most natural
+                // one to cut, and `tally` simply omits them
+                let continuing: Vec<(&str, f64)> = candidates
+                    .iter()
+                    .filter(|c| !eliminated.contains(*c) && !elected.contains(c))
+                    .map(|c| (*c, *tally.get(c).unwrap_or(&0.0)))
+                    .collect();
+
+                let min_votes = continuing.iter().map(|(_, v)| *v).fold(f64::MAX, f64::min);
+                let tied: Vec<&str> = continuing
+                    .iter()
+                    .filter(|(_, v)| (*v - min_votes).abs() < 1e-9)
+                    .map(|(c, _)| *c)
+                    .collect();
+
+                if tied.is_empty() {
+                    break;
+                }
+
+                let loser = self.resolve_tie(rounds.len(), &tied, true);
+                eliminated.insert(loser);
+            }
+        }
+
+        (elected, rounds)
+    }
+}
+
+/// Adapts `VoteData`'s weighted ballots into ranked ballots, the same way
+/// `crate::rcv`'s adapter does: each voter's policies are ranked by
+/// descending weight, and every policy `Uuid` is rendered to a `String`
+/// once so the STV backends can borrow it as `&str`.
+fn ranked_ballots(vote: &VoteData) -> (BTreeMap<Uuid, String>, Vec<Vec<Uuid>>) {
+    let ids: BTreeMap<Uuid, String> = vote.policies.iter().map(|id| (*id, id.to_string())).collect();
+
+    let ballots = vote
+        .only_policy_voting()
+        .into_values()
+        .map(|choices| {
+            let mut ranked: Vec<(Uuid, f64)> = choices.into_iter().collect();
+            ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+            ranked.into_iter().map(|(id, _)| id).collect()
+        })
+        .collect();
+
+    (ids, ballots)
+}
+
+/// `AggregationRule`'s contract takes no seat count, so the trait entry
+/// point elects a single seat, seeded from `VoteData::hash_sync()` like the
+/// other ranked-ballot modules; a caller that needs a full committee
+/// constructs `StvVoting` directly with an explicit `seats`.
+#[async_trait]
+impl AggregationRule for StvVoting<'_> {
+    async fn calculate(vote: VoteData) -> Value {
+        let (ids, ballots) = ranked_ballots(&vote);
+        let ballots: Vec<Vec<&str>> = ballots
+            .iter()
+            .map(|ballot| ballot.iter().map(|id| ids[id].as_str()).collect())
+            .collect();
+
+        let mut stv = StvVoting::new(ballots, 1);
+        stv.seed(vote.hash_sync());
+        let (winners, rounds) = stv.calculate();
+
+        let winners: Vec<Uuid> = winners.into_iter().filter_map(|id| Uuid::parse_str(id).ok()).collect();
+        let rounds: Vec<BTreeMap<Uuid, f64>> = rounds
+            .into_iter()
+            .map(|round| {
+                round
+                    .into_iter()
+                    .filter_map(|(id, tally)| Uuid::parse_str(id).ok().map(|id| (id, tally)))
+                    .collect()
+            })
+            .collect();
+
+        json!({ "winners": winners, "rounds": rounds })
+    }
+}
+
+/// Meek's method: an alternative STV counting backend that recomputes the
+/// full ballot distribution on every iteration instead of transferring
+/// surplus in discrete Gregory rounds, which handles exhausted ballots more
+/// fairly than [`StvVoting`].
+pub struct MeekStv<'a> {
+    voters: Vec<Vec<&'a str>>,
+    seats: usize,
+    tolerance: f64,
+    max_iterations: usize,
+}
+
+impl<'a> MeekStv<'a> {
+    pub fn new(voters: Vec<Vec<&'a str>>, seats: usize) -> Self {
+        Self {
+            voters,
+            seats,
+            tolerance: 1e-9,
+            max_iterations: 1_000,
+        }
+    }
+
+    pub fn tolerance(&mut self, tolerance: f64) {
+        self.tolerance = tolerance;
+    }
+
+    pub fn max_iterations(&mut self, max_iterations: usize) {
+        self.max_iterations = max_iterations;
+    }
+
+    /// Distributes every ballot in full according to the current keep
+    /// values: a continuing candidate `c` retains `weight * k_c` of a
+    /// ballot's weight and passes the rest on to the next preference,
+    /// weight reaching the end of the list (or an excluded candidate at the
+    /// end) is exhausted.
+    fn distribute(
+        &self,
+        keep: &HashMap<&'a str, f64>,
+        excluded: &HashSet<&'a str>,
+    ) -> (HashMap<&'a str, f64>, f64) {
+        let mut received: HashMap<&str, f64> = HashMap::new();
+        let mut non_exhausted_total = 0.0;
+
+        for ballot in &self.voters {
+            let mut weight = 1.0;
+
+            for &candidate in ballot {
+                if excluded.contains(candidate) || weight <= 0.0 {
+                    continue;
+                }
+
+                let k = *keep.get(candidate).unwrap_or(&0.0);
+                let take = weight * k;
+                *received.entry(candidate).or_insert(0.0) += take;
+                non_exhausted_total += take;
+                weight -= take;
+            }
+        }
+
+        (received, non_exhausted_total)
+    }
+
+    /// Runs the count, returning the winners in order of election alongside
+    /// the keep values at the point the count settled, so a caller can
+    /// audit how much of each elected candidate's vote was retained.
+    pub fn calculate(&self) -> (Vec<&'a str>, HashMap<&'a str, f64>) {
+        let candidates: BTreeSet<&str> = self.voters.iter().flatten().cloned().collect();
+
+        let mut keep: HashMap<&str, f64> = candidates.iter().map(|&c| (c, 1.0)).collect();
+        let mut excluded: HashSet<&str> = HashSet::new();
+        let mut elected: Vec<&str> = Vec::new();
+
+        while elected.len() < self.seats {
+            let continuing = candidates.len() - excluded.len() - elected.len();
+            if continuing <= self.seats - elected.len() {
+                let (received, _) = self.distribute(&keep, &excluded);
+                let mut remaining: Vec<&str> = candidates
+                    .iter()
+                    .filter(|c| !excluded.contains(*c) && !elected.contains(c))
+                    .cloned()
+                    .collect();
+                remaining.sort_by(|a, b| {
+                    received
+                        .get(b)
+                        .unwrap_or(&0.0)
+                        .partial_cmp(received.get(a).unwrap_or(&0.0))
+                        .unwrap()
+                });
+                elected.extend(remaining);
+                break;
+            }
+
+            let (mut received, mut non_exhausted_total) = self.distribute(&keep, &excluded);
+            let mut quota = non_exhausted_total / (self.seats + 1) as f64;
+
+            for _ in 0..self.max_iterations {
+                let max_excess = elected
+                    .iter()
+                    .map(|c| (received.get(c).unwrap_or(&0.0) - quota).max(0.0))
+                    .fold(0.0, f64::max);
+
+                if max_excess < self.tolerance {
+                    break;
+                }
+
+                for &c in &elected {
+                    let v = *received.get(c).unwrap_or(&0.0);
+                    if v > quota {
+                        *keep.get_mut(c).unwrap() *= quota / v;
+                    }
+                }
+
+                let (r, t) = self.distribute(&keep, &excluded);
+                received = r;
+                non_exhausted_total = t;
+                quota = non_exhausted_total / (self.seats + 1) as f64;
+            }
+
+            let mut newly_elected: Vec<&str> = candidates
+                .iter()
+                .filter(|c| !excluded.contains(*c) && !elected.contains(c))
+                .filter(|c| *received.get(*c).unwrap_or(&0.0) >= quota)
+                .cloned()
+                .collect();
+
+            // candidates sitting exactly on the quota can outnumber the
+            // seats left, so only the strongest fill the remaining seats
+            newly_elected.sort_by(|a, b| {
+                received
+                    .get(b)
+                    .unwrap_or(&0.0)
+                    .partial_cmp(received.get(a).unwrap_or(&0.0))
+                    .unwrap()
+            });
+            newly_elected.truncate(self.seats - elected.len());
+
+            if !newly_elected.is_empty() {
+                elected.extend(newly_elected);
+                continue;
+            }
+
+            let loser = candidates
+                .iter()
+                .filter(|c| !excluded.contains(*c) && !elected.contains(c))
+                .min_by(|a, b| {
+                    received
+                        .get(*a)
+                        .unwrap_or(&0.0)
+                        .partial_cmp(received.get(*b).unwrap_or(&0.0))
+                        .unwrap()
+                })
+                .cloned();
+
+            match loser {
+                Some(loser) => {
+                    excluded.insert(loser);
+                    *keep.get_mut(loser).unwrap() = 0.0;
+                }
+                None => break,
+            }
+        }
+
+        (elected, keep)
+    }
+}
+
+/// `AggregationRule`'s contract takes no seat count, so the trait entry
+/// point elects a single seat; a caller that needs a full committee
+/// constructs `MeekStv` directly with an explicit `seats`.
+#[async_trait]
+impl AggregationRule for MeekStv<'_> {
+    async fn calculate(vote: VoteData) -> Value {
+        let (ids, ballots) = ranked_ballots(&vote);
+        let ballots: Vec<Vec<&str>> = ballots
+            .iter()
+            .map(|ballot| ballot.iter().map(|id| ids[id].as_str()).collect())
+            .collect();
+
+        let meek = MeekStv::new(ballots, 1);
+        let (winners, keep) = meek.calculate();
+
+        let winners: Vec<Uuid> = winners.into_iter().filter_map(|id| Uuid::parse_str(id).ok()).collect();
+        let keep: BTreeMap<Uuid, f64> = keep
+            .into_iter()
+            .filter_map(|(id, k)| Uuid::parse_str(id).ok().map(|id| (id, k)))
+            .collect();
+
+        json!({ "winners": winners, "keep": keep })
+    }
+}
+
+#[cfg(test)]
+mod meek_stv_test {
+
+    use super::*;
+    use crate::TopicData;
+    use futures::executor::block_on;
+
+    #[test]
+    fn single_seat_majority() {
+        let a = vec!["dog", "cat"];
+        let b = vec!["dog", "bat"];
+        let c = vec!["cat", "dog"];
+
+        let voters: Vec<Vec<&str>> = vec![a, b, c];
+        let meek = MeekStv::new(voters, 1);
+        let (winners, _keep) = meek.calculate();
+
+        assert_eq!(winners, vec!["dog"]);
+    }
+
+    #[test]
+    fn two_seats_elect_both_front_runners() {
+        let a = vec!["dog", "cat"];
+        let b = vec!["dog", "bat"];
+        let c = vec!["cat", "dog"];
+        let d = vec!["cat", "bat"];
+        let e = vec!["bat"];
+
+        let voters: Vec<Vec<&str>> = vec![a, b, c, d, e];
+        let meek = MeekStv::new(voters, 2);
+        let (winners, _keep) = meek.calculate();
+
+        assert_eq!(winners.len(), 2);
+        assert!(winners.contains(&"dog"));
+        assert!(winners.contains(&"cat"));
+    }
+
+    #[test]
+    fn aggregation_rule_picks_the_heaviest_weighted_policy() {
+        let mut topic = TopicData::dummy();
+
+        let alice = topic.get_id_by_name("alice").unwrap();
+        let apples = topic.get_id_by_title("apples").unwrap();
+        let bananas = topic.get_id_by_title("bananas").unwrap();
+
+        // alice now prefers bananas over apples
+        topic.cast_vote_to(&alice, &apples, 0.2f64);
+        topic.cast_vote_to(&alice, &bananas, 0.8f64);
+
+        let info: VoteData = topic.into();
+        let result = block_on(<MeekStv as AggregationRule>::calculate(info));
+
+        let winners = result.get("winners").unwrap().as_array().unwrap();
+        assert_eq!(winners.len(), 1);
+        assert_eq!(winners[0].as_str().unwrap(), bananas.to_string());
+    }
+}
+
+#[cfg(test)]
+mod stv_test {
+
+    use super::*;
+    use crate::TopicData;
+    use futures::executor::block_on;
+
+    #[test]
+    fn single_seat_majority() {
+        let a = vec!["dog", "cat"];
+        let b = vec!["dog", "bat"];
+        let c = vec!["cat", "dog"];
+
+        let voters: Vec<Vec<&str>> = vec![a, b, c];
+        let stv = StvVoting::new(voters, 1);
+        let (winners, _rounds) = stv.calculate();
+
+        assert_eq!(winners, vec!["dog"]);
+    }
+
+    #[test]
+    fn two_seats_with_surplus_transfer() {
+        let a = vec!["dog", "cat"];
+        let b = vec!["dog", "bat"];
+        let c = vec!["dog", "cat"];
+        let d = vec!["cat", "dog"];
+        let e = vec!["bat"];
+
+        let voters: Vec<Vec<&str>> = vec![a, b, c, d, e];
+        let stv = StvVoting::new(voters, 2);
+        let (winners, rounds) = stv.calculate();
+
+        assert_eq!(winners.len(), 2);
+        assert!(winners.contains(&"dog"));
+        assert!(!rounds.is_empty());
+    }
+
+    #[test]
+    fn zero_tally_candidate_is_eliminated_before_a_real_contender() {
+        // "nobody" is a valid candidate (it appears on f's ballot) but is
+        // never anyone's current preference while "cat" stays in the race,
+        // so its tally is always absent from `tally`, not just low. If the
+        // elimination pool is built from `tally` alone, "nobody" is invisible
+        // to it; once every other candidate but "cat" is eliminated, "cat"
+        // becomes the *sole* entry in that pool and gets wrongly eliminated
+        // for being its own minimum, handing the seat to "nobody" instead.
+        let a = vec!["dog"];
+        let b = vec!["cat"];
+        let c = vec!["cat"];
+        let f = vec!["cat", "nobody"];
+        let d = vec!["bat"];
+        let e = vec!["bat"];
+
+        let voters: Vec<Vec<&str>> = vec![a, b, c, f, d, e];
+        let stv = StvVoting::new(voters, 1);
+        let (winners, _rounds) = stv.calculate();
+
+        assert_eq!(winners, vec!["cat"]);
+    }
+
+    #[test]
+    fn seeded_tie_break_is_deterministic() {
+        let a = vec!["dog"];
+        let b = vec!["cat"];
+
+        let voters: Vec<Vec<&str>> = vec![a, b];
+        let mut stv = StvVoting::new(voters, 1);
+        stv.seed(b"published-digest".to_vec());
+
+        let (first, _) = stv.calculate();
+        let (second, _) = stv.calculate();
+
+        assert_eq!(first, second);
+        assert_eq!(first.len(), 1);
+    }
+
+    #[test]
+    fn remaining_seats_fill_when_candidates_run_out() {
+        let a = vec!["dog"];
+        let b = vec!["cat"];
+
+        let voters: Vec<Vec<&str>> = vec![a, b];
+        let stv = StvVoting::new(voters, 2);
+        let (winners, _rounds) = stv.calculate();
+
+        assert_eq!(winners.len(), 2);
+        assert!(winners.contains(&"dog"));
+        assert!(winners.contains(&"cat"));
+    }
+
+    #[test]
+    fn aggregation_rule_picks_the_heaviest_weighted_policy() {
+        let mut topic = TopicData::dummy();
+
+        let alice = topic.get_id_by_name("alice").unwrap();
+        let apples = topic.get_id_by_title("apples").unwrap();
+        let bananas = topic.get_id_by_title("bananas").unwrap();
+
+        // alice now prefers bananas over apples
+        topic.cast_vote_to(&alice, &apples, 0.2f64);
+        topic.cast_vote_to(&alice, &bananas, 0.8f64);
+
+        let info: VoteData = topic.into();
+        let result = block_on(<StvVoting as AggregationRule>::calculate(info));
+
+        let winners = result.get("winners").unwrap().as_array().unwrap();
+        assert_eq!(winners.len(), 1);
+        assert_eq!(winners[0].as_str().unwrap(), bananas.to_string());
+    }
+}
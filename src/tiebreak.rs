@@ -0,0 +1,71 @@
+use sha2::{Digest, Sha256};
+
+/// Deterministically resolves a tie among `candidates`, seeded from a
+/// published digest (typically `VoteData::hash_sync()`) and the current
+/// round index, so any observer can independently recompute the same
+/// result from the published ballots.
+///
+/// Candidates are first sorted by identifier for a stable starting order,
+/// then ranked by `SHA256(seed || round || candidate)`. Pass `lowest = true`
+/// to resolve an elimination tie (the lowest digest is eliminated) or
+/// `false` to resolve an election tie (the highest digest is elected).
+pub fn break_tie<'a>(seed: &[u8], round: usize, candidates: &[&'a str], lowest: bool) -> &'a str {
+    let mut ranked: Vec<&'a str> = candidates.to_vec();
+    ranked.sort();
+
+    let digest = |candidate: &str| -> Vec<u8> {
+        let mut hasher = Sha256::new();
+        hasher.update(seed);
+        hasher.update((round as u64).to_be_bytes());
+        hasher.update(candidate.as_bytes());
+        hasher.finalize().to_vec()
+    };
+
+    if lowest {
+        ranked.into_iter().min_by_key(|c| digest(c)).unwrap()
+    } else {
+        ranked.into_iter().max_by_key(|c| digest(c)).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tiebreak_test {
+
+    use super::*;
+
+    #[test]
+    fn deterministic_across_calls() {
+        let seed = b"some-published-digest";
+        let candidates = ["dog", "cat", "bat"];
+
+        let first = break_tie(seed, 0, &candidates, true);
+        let second = break_tie(seed, 0, &candidates, true);
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn lowest_and_highest_can_differ() {
+        let seed = b"some-published-digest";
+        let candidates = ["dog", "cat", "bat"];
+
+        let eliminated = break_tie(seed, 0, &candidates, true);
+        let elected = break_tie(seed, 0, &candidates, false);
+
+        assert!(candidates.contains(&eliminated));
+        assert!(candidates.contains(&elected));
+    }
+
+    #[test]
+    fn round_index_changes_the_outcome_space() {
+        let seed = b"some-published-digest";
+        let candidates = ["dog", "cat"];
+
+        let round_0 = break_tie(seed, 0, &candidates, true);
+        let round_1 = break_tie(seed, 1, &candidates, true);
+
+        // not asserting they differ (they may coincide), just that both are valid
+        assert!(candidates.contains(&round_0));
+        assert!(candidates.contains(&round_1));
+    }
+}